@@ -3,105 +3,709 @@ use std::time::{SystemTime, Duration, UNIX_EPOCH};
 use std::io::{Error, ErrorKind};
 use std::path::Path;
 use std::env::temp_dir;
+use std::sync::{Arc, Mutex, Weak, OnceLock};
 
+mod sha256;
+
+/// Produce a process-unique suffix for staging files used by atomic writes
+fn next_nonce() -> String {
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    let count = COUNTER.fetch_add(1, Ordering::Relaxed);
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_nanos())
+        .unwrap_or(0);
+
+    format!("{nanos:x}-{count:x}")
+}
+
+/// Write `bytes` to `path` atomically, via a sibling staging file and a rename
+///
+/// The bytes are written to a `<path>.partial-<nonce>` file in the same
+/// directory, flushed to disk, and then renamed onto `path` in a single
+/// syscall, so readers only ever observe complete content and concurrent
+/// writers of the same target don't clobber each other mid-write. `rename` is
+/// atomic only within one filesystem, hence the staging file lives next to the
+/// target.
+fn write_atomic(path: &str, bytes: &[u8]) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let staging = format!("{path}.partial-{}", next_nonce());
+
+    let mut file = std::fs::File::create(&staging)?;
+
+    file.write_all(bytes)?;
+    file.sync_all()?;
+
+    std::fs::rename(&staging, path)
+}
+
+/// Like [`write_atomic`], but restricts the file to the owner
+///
+/// On Unix the staging file is created with `0o600` before any bytes are
+/// written, so the secret is never readable by other local users, even in the
+/// window between creation and rename. On other platforms this falls back to
+/// the default ACL.
+fn write_atomic_private(path: &str, bytes: &[u8]) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let staging = format!("{path}.partial-{}", next_nonce());
+
+    let mut options = std::fs::OpenOptions::new();
+
+    options.write(true).create(true).truncate(true);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+
+        options.mode(0o600);
+    }
+
+    let mut file = options.open(&staging)?;
+
+    file.write_all(bytes)?;
+    file.sync_all()?;
+
+    std::fs::rename(&staging, path)
+}
+
+/// Restrict a directory to the owner (`0o700` on Unix, default ACL elsewhere)
+fn make_dir_private(path: &str) -> std::io::Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o700))?;
+    }
+
+    #[cfg(not(unix))]
+    let _ = path;
+
+    Ok(())
+}
+
+/// Restrict an already-written file to the owner (`0o600` on Unix, default ACL
+/// elsewhere)
+///
+/// Used when `map_private` finds the target already mapped (by a prior
+/// `map()`, or by another content-addressed entry of the same bytes): the file
+/// predates the private request and so wasn't created with a restrictive mode,
+/// and must be tightened in place instead of silently being handed back loose.
+fn make_file_private(path: &str) -> std::io::Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+    }
+
+    #[cfg(not(unix))]
+    let _ = path;
+
+    Ok(())
+}
+
+/// Storage location for mapped entries
+///
+/// A backend owns the actual placement of bytes on (or off) disk. `Storage` and
+/// `Entry` are parameterized over it, so the same mapping logic can target the
+/// system temp directory ([`TempDirBackend`], the default), a caller-chosen
+/// directory ([`DirBackend`]), or plain memory for tests ([`MemoryBackend`]).
+pub trait Backend: Send + Sync + 'static {
+    /// Resolve the path the given id maps to
+    fn path(&self, random_id: &str) -> String;
+
+    /// Write `bytes` for the given id, returning the mapped path
+    fn write(&self, random_id: &str, bytes: &[u8]) -> std::io::Result<String>;
+
+    /// Write `bytes` for the given id, restricted to the owner
+    ///
+    /// Backends that place bytes on disk should create the file (and any parent
+    /// directory) readable only by the owner. The default implementation just
+    /// delegates to [`write`](Backend::write), which is correct for backends
+    /// where permissions don't apply (e.g. [`MemoryBackend`]).
+    fn write_private(&self, random_id: &str, bytes: &[u8]) -> std::io::Result<String> {
+        self.write(random_id, bytes)
+    }
+
+    /// Remove the mapped file for the given id
+    ///
+    /// Returns `Ok(())` if the id isn't currently mapped.
+    fn remove(&self, random_id: &str) -> std::io::Result<()>;
+
+    /// Check whether the given id is currently mapped
+    fn exists(&self, random_id: &str) -> bool {
+        Path::new(&self.path(random_id)).exists()
+    }
+
+    /// Tighten an already-mapped id to owner-only access
+    ///
+    /// Called by [`Entry::map_private`] when `exists` is already true, so the
+    /// file predates the private request (written by a prior plain `write`, or
+    /// by another entry sharing the same content-addressed path) and wasn't
+    /// created with a restrictive mode. The default implementation is a no-op,
+    /// which is correct for backends where permissions don't apply (e.g.
+    /// [`MemoryBackend`]).
+    fn make_private(&self, random_id: &str) -> std::io::Result<()> {
+        let _ = random_id;
+
+        Ok(())
+    }
+}
+
+/// Backend mapping entries into the system temp directory
+///
+/// This is the default backend and reproduces the crate's original behaviour.
+#[derive(Debug, Clone, Default)]
+pub struct TempDirBackend;
+
+impl Backend for TempDirBackend {
+    fn path(&self, random_id: &str) -> String {
+        format!("{}/{}", temp_dir().to_string_lossy(), random_id)
+    }
+
+    fn write(&self, random_id: &str, bytes: &[u8]) -> std::io::Result<String> {
+        let path = self.path(random_id);
+
+        write_atomic(&path, bytes)?;
+
+        Ok(path)
+    }
+
+    fn write_private(&self, random_id: &str, bytes: &[u8]) -> std::io::Result<String> {
+        let path = self.path(random_id);
+
+        write_atomic_private(&path, bytes)?;
+
+        Ok(path)
+    }
+
+    fn remove(&self, random_id: &str) -> std::io::Result<()> {
+        let path = self.path(random_id);
+
+        if Path::new(&path).exists() {
+            std::fs::remove_file(path)
+        }
+
+        else {
+            Ok(())
+        }
+    }
+
+    fn make_private(&self, random_id: &str) -> std::io::Result<()> {
+        make_file_private(&self.path(random_id))
+    }
+}
+
+/// Backend mapping entries into a caller-chosen base directory
 #[derive(Debug, Clone)]
-pub struct Entry {
+pub struct DirBackend {
+    base: String
+}
+
+impl DirBackend {
+    /// Map entries into `base`, creating the directory on first write
+    pub fn new<P: Into<String>>(base: P) -> Self {
+        Self { base: base.into() }
+    }
+
+    /// Map entries into a directory resolved relative to the current executable
+    ///
+    /// Useful for keeping mapped files inside an app-private directory that
+    /// travels with the binary instead of the shared system temp directory.
+    pub fn next_to_exe<P: AsRef<Path>>(relative: P) -> std::io::Result<Self> {
+        let exe = std::env::current_exe()?;
+        let base = exe.parent()
+            .unwrap_or_else(|| Path::new("."))
+            .join(relative);
+
+        Ok(Self { base: base.to_string_lossy().into_owned() })
+    }
+}
+
+impl Backend for DirBackend {
+    fn path(&self, random_id: &str) -> String {
+        format!("{}/{}", self.base, random_id)
+    }
+
+    fn write(&self, random_id: &str, bytes: &[u8]) -> std::io::Result<String> {
+        std::fs::create_dir_all(&self.base)?;
+
+        let path = self.path(random_id);
+
+        write_atomic(&path, bytes)?;
+
+        Ok(path)
+    }
+
+    fn write_private(&self, random_id: &str, bytes: &[u8]) -> std::io::Result<String> {
+        std::fs::create_dir_all(&self.base)?;
+        make_dir_private(&self.base)?;
+
+        let path = self.path(random_id);
+
+        write_atomic_private(&path, bytes)?;
+
+        Ok(path)
+    }
+
+    fn remove(&self, random_id: &str) -> std::io::Result<()> {
+        let path = self.path(random_id);
+
+        if Path::new(&path).exists() {
+            std::fs::remove_file(path)
+        }
+
+        else {
+            Ok(())
+        }
+    }
+
+    fn make_private(&self, random_id: &str) -> std::io::Result<()> {
+        make_dir_private(&self.base)?;
+        make_file_private(&self.path(random_id))
+    }
+}
+
+/// In-memory backend that never touches the real filesystem
+///
+/// Intended for tests: mapped bytes are kept in a shared map and can be read
+/// back with [`MemoryBackend::read`]. Cloning the backend shares the same
+/// storage, matching the reference-counted sharing of [`Entry`].
+///
+/// ```
+/// use kinda_virtual_fs::*;
+///
+/// let backend = MemoryBackend::new();
+/// let entry = Entry::new_content_addressed_in(backend.clone(), "Hello, World!");
+///
+/// let path = entry.map().unwrap();
+///
+/// assert!(path.starts_with("memory://"));
+/// assert_eq!(backend.read(&format!("{}.kvfs", entry.hash())), Some(b"Hello, World!".to_vec()));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MemoryBackend {
+    store: Arc<Mutex<HashMap<String, Vec<u8>>>>
+}
+
+impl MemoryBackend {
+    /// Create an empty in-memory backend
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read the bytes currently mapped for the given id, if any
+    pub fn read(&self, random_id: &str) -> Option<Vec<u8>> {
+        self.store.lock().unwrap().get(random_id).cloned()
+    }
+}
+
+impl Backend for MemoryBackend {
+    fn path(&self, random_id: &str) -> String {
+        format!("memory://{random_id}")
+    }
+
+    fn write(&self, random_id: &str, bytes: &[u8]) -> std::io::Result<String> {
+        self.store.lock().unwrap().insert(random_id.to_string(), bytes.to_vec());
+
+        Ok(self.path(random_id))
+    }
+
+    fn remove(&self, random_id: &str) -> std::io::Result<()> {
+        self.store.lock().unwrap().remove(random_id);
+
+        Ok(())
+    }
+
+    fn exists(&self, random_id: &str) -> bool {
+        self.store.lock().unwrap().contains_key(random_id)
+    }
+}
+
+/// Derive a time-based file name from a creation time and content length
+fn time_based_id(created: SystemTime, len: usize) -> String {
+    let micros = created
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or(Duration::from_secs(0))
+        .as_micros();
+
+    format!("{micros:x}-{len:x}.kvfs")
+}
+
+/// Type-erased teardown for a single mapped physical path
+///
+/// Every [`Entry`] holds its file state behind an `Arc<PathGuard>`, so cloning
+/// an entry shares the same guard. The real removal only happens when the last
+/// holder of a given guard is dropped, which keeps auto-cleanup working while
+/// making clones (and the content-addressed mode, where many entries share one
+/// path) safe to share across threads and storages.
+///
+/// Guards are keyed by mapped path in a process-wide registry, so even entries
+/// constructed *independently* that resolve to the same path — as two
+/// content-addressed entries of identical bytes do, even when they're backed
+/// by *different* concrete [`Backend`] types — share one guard rather than
+/// racing to unlink each other's file. Keying (and removal) is done purely by
+/// path string rather than by downcasting to a concrete `Backend`, which is
+/// what makes the cross-type case safe: a per-type registry would let two
+/// different `Backend` implementations that happen to resolve to the same
+/// path each believe they own an independent guard.
+struct PathGuard {
+    path: String,
+    remove: Box<dyn Fn() -> std::io::Result<()> + Send + Sync>
+}
+
+impl std::fmt::Debug for PathGuard {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("PathGuard")
+            .field("path", &self.path)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for PathGuard {
+    fn drop(&mut self) {
+        // Drop only runs once the last `Arc` holding this guard is gone, so the
+        // registry entry for our path is now dead; clear it unless a newer guard
+        // has already taken the slot for a reused path. The physical file must be
+        // removed under the very same lock guard: releasing the lock between the
+        // liveness check and the `remove()` would let `shared_path_guard` register
+        // a fresh guard for the same path (and `map()` it back onto disk) before
+        // we unlink it, deleting a file a live holder believes is still mapped.
+        if let Ok(mut registry) = guard_registry().lock() {
+            if registry.get(&self.path).map(|weak| weak.strong_count() == 0).unwrap_or(false) {
+                registry.remove(&self.path);
+
+                let _ = (self.remove)();
+            }
+        }
+    }
+}
+
+/// Process-wide registry of live [`PathGuard`]s, keyed by mapped path
+///
+/// Guards are stored as `Weak` references so the registry never keeps a file
+/// mapped on its own; dead entries are pruned lazily on drop and on lookup.
+fn guard_registry() -> &'static Mutex<HashMap<String, Weak<PathGuard>>> {
+    static REGISTRY: OnceLock<Mutex<HashMap<String, Weak<PathGuard>>>> = OnceLock::new();
+
+    REGISTRY.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Fetch the shared [`PathGuard`] for `path`, inserting a fresh one if none
+///
+/// `remove` is only ever invoked for the guard that actually gets created here
+/// (the first caller for a given path); later callers that find a live guard
+/// already registered get it back as-is and their own `remove` is dropped
+/// unused, which is why it must be equivalent for every caller that can
+/// resolve to the same path.
+fn shared_path_guard(path: String, remove: impl Fn() -> std::io::Result<()> + Send + Sync + 'static) -> Arc<PathGuard> {
+    let mut registry = guard_registry().lock().unwrap();
+
+    if let Some(guard) = registry.get(&path).and_then(Weak::upgrade) {
+        return guard;
+    }
+
+    let guard = Arc::new(PathGuard { path: path.clone(), remove: Box::new(remove) });
+
+    registry.insert(path, Arc::downgrade(&guard));
+
+    guard
+}
+
+#[derive(Debug)]
+pub struct Entry<B: Backend = TempDirBackend> {
     pub bytes: Vec<u8>,
-    random_id: String
+    backend: Arc<B>,
+    random_id: String,
+    guard: Arc<PathGuard>,
+    hash: String,
+    created: SystemTime,
+    ttl: Option<Duration>
 }
 
-impl Entry {
+// Written by hand instead of `#[derive(Clone)]`: a derived impl would add a
+// spurious `B: Clone` bound even though cloning only ever touches `Arc`s and
+// owned scalars, none of which need the backend itself to be `Clone`.
+impl<B: Backend> Clone for Entry<B> {
+    fn clone(&self) -> Self {
+        Self {
+            bytes: self.bytes.clone(),
+            backend: Arc::clone(&self.backend),
+            random_id: self.random_id.clone(),
+            guard: Arc::clone(&self.guard),
+            hash: self.hash.clone(),
+            created: self.created,
+            ttl: self.ttl
+        }
+    }
+}
+
+impl Entry<TempDirBackend> {
     /// Create new entry
+    ///
+    /// The on-disk file name is derived from the creation time and the length
+    /// of `bytes`, so separate `Entry::new` calls normally map to separate
+    /// physical files; two entries of the same length created within the same
+    /// microsecond resolve to one shared file. Use [`Entry::new_content_addressed`] if you want identical content to
+    /// share a single file instead.
     pub fn new<T: Into<Vec<u8>>>(bytes: T) -> Self {
-        let timestamp = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap_or(Duration::from_secs(0));
+        Self::new_in(TempDirBackend, bytes)
+    }
 
-        let bytes = bytes.into().to_vec();
-        let random_id = format!("{:x}-{:x}.kvfs", timestamp.as_micros(), bytes.len());
+    /// Create new content-addressed entry
+    ///
+    /// The on-disk file name is derived from the SHA-256 digest of `bytes`
+    /// instead of the creation time, so two entries holding identical content
+    /// map to exactly one physical file. This makes [`map`](Entry::map)
+    /// idempotent and collision-free and gives free deduplication across a
+    /// [`Storage`].
+    ///
+    /// Because several entries may now legitimately share a single path,
+    /// dropping one of them must not unlink the file out from under the
+    /// others — see the reference-counting behaviour of [`Entry`].
+    ///
+    /// ```
+    /// use kinda_virtual_fs::*;
+    ///
+    /// let a = Entry::new_content_addressed("Hello, World!");
+    /// let b = Entry::new_content_addressed("Hello, World!");
+    ///
+    /// assert_eq!(a.hash(), b.hash());
+    /// assert_eq!(a.map().unwrap(), b.map().unwrap());
+    /// ```
+    pub fn new_content_addressed<T: Into<Vec<u8>>>(bytes: T) -> Self {
+        Self::new_content_addressed_in(TempDirBackend, bytes)
+    }
+
+    /// Create new entry that expires after `ttl`
+    ///
+    /// The creation time is stamped at construction. Once `ttl` has elapsed the
+    /// entry is treated as absent: [`map`](Entry::map) unmaps any stale physical
+    /// file and returns a not-found error, and [`Storage::purge_expired`] drops
+    /// it. Useful for time-bounded data like rendered templates or downloaded
+    /// assets that shouldn't accumulate on disk.
+    pub fn new_with_ttl<T: Into<Vec<u8>>>(bytes: T, ttl: Duration) -> Self {
+        Self::new_with_ttl_in(TempDirBackend, bytes, ttl)
+    }
+}
+
+impl<B: Backend> Entry<B> {
+    fn build(backend: B, bytes: Vec<u8>, random_id: String, hash: String, created: SystemTime, ttl: Option<Duration>) -> Self {
+        let backend = Arc::new(backend);
+        let path = backend.path(&random_id);
+
+        let guard = {
+            let backend = Arc::clone(&backend);
+            let random_id = random_id.clone();
+
+            shared_path_guard(path, move || backend.remove(&random_id))
+        };
 
         Self {
             bytes,
-            random_id
+            backend,
+            random_id,
+            guard,
+            hash,
+            created,
+            ttl
         }
     }
 
-    fn get_temp_path(&self) -> String {
-        format!("{}/{}", temp_dir().to_string_lossy(), self.random_id)
+    /// Create new entry mapped through a custom [`Backend`]
+    pub fn new_in<T: Into<Vec<u8>>>(backend: B, bytes: T) -> Self {
+        let created = SystemTime::now();
+        let bytes = bytes.into().to_vec();
+        let hash = sha256::hex_digest(&bytes);
+        let random_id = time_based_id(created, bytes.len());
+
+        Self::build(backend, bytes, random_id, hash, created, None)
+    }
+
+    /// Create new entry mapped through a custom [`Backend`] that expires after `ttl`
+    pub fn new_with_ttl_in<T: Into<Vec<u8>>>(backend: B, bytes: T, ttl: Duration) -> Self {
+        let created = SystemTime::now();
+        let bytes = bytes.into().to_vec();
+        let hash = sha256::hex_digest(&bytes);
+        let random_id = time_based_id(created, bytes.len());
+
+        Self::build(backend, bytes, random_id, hash, created, Some(ttl))
+    }
+
+    /// Create new content-addressed entry mapped through a custom [`Backend`]
+    pub fn new_content_addressed_in<T: Into<Vec<u8>>>(backend: B, bytes: T) -> Self {
+        let created = SystemTime::now();
+        let bytes = bytes.into().to_vec();
+        let hash = sha256::hex_digest(&bytes);
+        let random_id = format!("{hash}.kvfs");
+
+        Self::build(backend, bytes, random_id, hash, created, None)
+    }
+
+    /// Get the SHA-256 digest of the entry's content, as a lowercase hex string
+    ///
+    /// Callers can use this to address entries by content regardless of how the
+    /// entry was constructed.
+    pub fn hash(&self) -> &str {
+        self.hash.as_str()
+    }
+
+    /// Whether the entry's time-to-live has elapsed
+    ///
+    /// Always `false` for entries created without a TTL. A clock that runs
+    /// backwards is treated as not expired.
+    fn is_expired(&self) -> bool {
+        match self.ttl {
+            Some(ttl) => self.created.elapsed().map(|elapsed| elapsed >= ttl).unwrap_or(false),
+            None => false
+        }
     }
 
     /// Map entry to physical location in your filesystem
-    /// 
+    ///
     /// Method returns path to the mapped file, or filesystem writing error
-    /// 
+    ///
+    /// Writes go through the entry's [`Backend`], which performs them
+    /// atomically, so readers never observe a partially written file.
+    ///
     /// ```
     /// use kinda_virtual_fs::*;
-    /// 
+    ///
     /// let entry = Entry::new("Hello, World!");
-    /// 
+    ///
     /// let file_path = entry.map().expect("Failed to map entry");
     /// let file_content = std::fs::read_to_string(file_path).expect("Failed to read mapped entry");
-    /// 
+    ///
     /// assert_eq!(&file_content, "Hello, World!");
     /// ```
     pub fn map(&self) -> std::io::Result<String> {
-        let path = self.get_temp_path();
+        if self.is_expired() {
+            let _ = self.unmap();
 
-        if !Path::new(&path).exists() {
-            std::fs::write(&path, self.bytes.as_slice())?;
+            return Err(Error::new(ErrorKind::NotFound, "entry has expired"));
         }
 
-        Ok(path)
+        let backend = self.backend.as_ref();
+        let id = &self.random_id;
+
+        // Every holder of this path shares one `PathGuard` (see
+        // `shared_path_guard`), so the short-circuit can't hand back a file a
+        // sibling's drop is about to unlink: the file stays mapped until the
+        // last holder is gone.
+        if backend.exists(id) {
+            Ok(backend.path(id))
+        }
+
+        else {
+            backend.write(id, self.bytes.as_slice())
+        }
+    }
+
+    /// Map entry to a physical location readable only by the owner
+    ///
+    /// Behaves like [`map`](Entry::map), but the mapped file (and the parent
+    /// directory of a custom directory backend) is restricted to the current
+    /// user: `0o600`/`0o700` on Unix, the default ACL on other platforms. Use
+    /// this when handing credentials or keys to a subprocess via a path so no
+    /// other local user can read them before [`unmap`](Entry::unmap).
+    ///
+    /// ```
+    /// use kinda_virtual_fs::*;
+    ///
+    /// let entry = Entry::new("secret");
+    ///
+    /// let path = entry.map_private().expect("Failed to map entry");
+    ///
+    /// assert_eq!(std::fs::read_to_string(&path).unwrap(), "secret");
+    ///
+    /// # #[cfg(unix)]
+    /// # {
+    /// use std::os::unix::fs::PermissionsExt;
+    ///
+    /// let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+    ///
+    /// assert_eq!(mode & 0o777, 0o600);
+    /// # }
+    /// ```
+    pub fn map_private(&self) -> std::io::Result<String> {
+        if self.is_expired() {
+            let _ = self.unmap();
+
+            return Err(Error::new(ErrorKind::NotFound, "entry has expired"));
+        }
+
+        let backend = self.backend.as_ref();
+        let id = &self.random_id;
+
+        // Every holder of this path shares one `PathGuard` (see
+        // `shared_path_guard`), so the short-circuit can't hand back a file a
+        // sibling's drop is about to unlink: the file stays mapped until the
+        // last holder is gone. The
+        // file may, however, already exist with a loose mode — from a prior
+        // plain `map()`, or from another content-addressed entry of the same
+        // bytes that mapped first — so it must still be tightened here rather
+        // than trusted as already private.
+        if backend.exists(id) {
+            backend.make_private(id)?;
+
+            Ok(backend.path(id))
+        }
+
+        else {
+            backend.write_private(id, self.bytes.as_slice())
+        }
     }
 
     /// Unmap (delete) entry from your filesystem
-    /// 
+    ///
     /// Entry will be automatically unmapped when its value is no more needed
-    /// 
+    ///
+    /// The physical file is reference counted: cloning an entry shares the
+    /// underlying file, and the automatic cleanup on drop only removes the file
+    /// once the last clone is gone. Calling `unmap` manually, however, deletes
+    /// the file immediately regardless of other holders.
+    ///
     /// ## Manual unmapping
-    /// 
+    ///
     /// ```
     /// use kinda_virtual_fs::*;
-    /// 
+    ///
     /// let entry = Entry::new("Hello, World!");
-    /// 
+    ///
     /// let path = entry.map().unwrap();
-    /// 
+    ///
     /// assert_eq!(std::path::Path::new(&path).exists(), true);
-    /// 
+    ///
     /// entry.unmap();
-    /// 
+    ///
     /// assert_eq!(std::path::Path::new(&path).exists(), false);
     /// ```
-    /// 
+    ///
     /// ## Automatic unmapping
-    /// 
+    ///
     /// ```
     /// use kinda_virtual_fs::*;
-    /// 
+    ///
     /// let path = {
     ///     let entry = Entry::new("Hello, World!");
     ///     let path = entry.map().unwrap();
-    /// 
+    ///
     ///     assert_eq!(std::path::Path::new(&path).exists(), true);
-    /// 
+    ///
     ///     path
     /// };
-    /// 
+    ///
     /// // entry is dropped here because it's no more used
-    /// 
+    ///
     /// assert_eq!(std::path::Path::new(&path).exists(), false);
     /// ```
     pub fn unmap(&self) -> std::io::Result<()> {
-        let path = self.get_temp_path();
-
-        if Path::new(&path).exists() {
-            std::fs::remove_file(path)
-        }
-
-        else {
-            Ok(())
-        }
+        self.backend.remove(&self.random_id)
     }
 
     /// Get bytes stored in entry
@@ -110,116 +714,190 @@ impl Entry {
     }
 }
 
-impl<T> From<T> for Entry where T: Into<Vec<u8>> {
+impl<T> From<T> for Entry<TempDirBackend> where T: Into<Vec<u8>> {
     fn from(bytes: T) -> Self {
         Self::new(bytes)
     }
 }
 
-impl Drop for Entry {
-    #[allow(unused_must_use)]
-    fn drop(&mut self) {
-        self.unmap();
+/// Magic tag at the start of a serialized [`Storage`] archive
+const ARCHIVE_MAGIC: &[u8; 4] = b"KVFS";
+
+/// Current archive layout version written by [`Storage::to_bytes`]
+const ARCHIVE_VERSION: u32 = 1;
+
+/// Cursor over a serialized archive, bounds-checking every read
+struct ArchiveReader<'a> {
+    data: &'a [u8],
+    pos: usize
+}
+
+impl<'a> ArchiveReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    fn take(&mut self, len: usize) -> std::io::Result<&'a [u8]> {
+        let end = self.pos.checked_add(len)
+            .filter(|end| *end <= self.data.len())
+            .ok_or_else(|| Error::new(ErrorKind::UnexpectedEof, "truncated kvfs archive"))?;
+
+        let slice = &self.data[self.pos..end];
+
+        self.pos = end;
+
+        Ok(slice)
+    }
+
+    fn take_u32(&mut self) -> std::io::Result<u32> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn take_u64(&mut self) -> std::io::Result<u64> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    /// Read a `u64` length prefix and narrow it to `usize`, rejecting archives
+    /// whose length fields overflow the platform's address space instead of
+    /// silently truncating them on 32-bit targets
+    fn take_len(&mut self) -> std::io::Result<usize> {
+        let len = self.take_u64()?;
+
+        usize::try_from(len)
+            .map_err(|_| Error::new(ErrorKind::InvalidData, "length field too large for this platform in kvfs archive"))
+    }
+
+    fn has_remaining(&self) -> bool {
+        self.pos < self.data.len()
     }
 }
 
-#[derive(Debug, Clone, Default)]
-pub struct Storage {
-    pub entries: HashMap<String, Entry>
+/// Read the version-1 record stream (length-prefixed key/value pairs)
+fn read_records_v1(reader: &mut ArchiveReader) -> std::io::Result<Vec<(String, Vec<u8>)>> {
+    let mut records = Vec::new();
+
+    while reader.has_remaining() {
+        let key_len = reader.take_len()?;
+        let key = reader.take(key_len)?.to_vec();
+        let key = String::from_utf8(key)
+            .map_err(|_| Error::new(ErrorKind::InvalidData, "non-utf8 key in kvfs archive"))?;
+
+        let value_len = reader.take_len()?;
+        let value = reader.take(value_len)?.to_vec();
+
+        records.push((key, value));
+    }
+
+    Ok(records)
+}
+
+#[derive(Debug, Clone)]
+pub struct Storage<B: Backend = TempDirBackend> {
+    pub entries: HashMap<String, Entry<B>>
 }
 
-impl Storage {
+// Written by hand instead of `#[derive(Default)]`: a derived impl would add a
+// spurious `B: Default` bound, even though an empty `HashMap` never needs its
+// value type to be `Default` — breaking `Storage::<DirBackend>::default()`
+// despite `DirBackend` having no sensible default base directory.
+impl<B: Backend> Default for Storage<B> {
+    fn default() -> Self {
+        Self { entries: HashMap::new() }
+    }
+}
+
+impl<B: Backend> Storage<B> {
     /// Create storage
-    pub fn new(entries: HashMap<String, Entry>) -> Self {
+    pub fn new(entries: HashMap<String, Entry<B>>) -> Self {
         Self { entries }
     }
 
     /// Add new entry to storage
-    /// 
+    ///
     /// Works as `HashMap::insert` method, so will return `Some(Entry)` if it replaced older value
-    /// 
+    ///
     /// ```
     /// use kinda_virtual_fs::*;
-    /// 
+    ///
     /// let mut storage = Storage::default();
-    /// 
+    ///
     /// storage.add("example 1", "Hello, World!");
     /// storage.add("example 2", Entry::new("Also accepts Entry struct"));
     /// ```
-    pub fn add<K: ToString, T: Into<Entry>>(&mut self, key: K, entry: T) -> Option<Entry> {
+    pub fn add<K: ToString, T: Into<Entry<B>>>(&mut self, key: K, entry: T) -> Option<Entry<B>> {
         self.entries.insert(key.to_string(), entry.into())
     }
 
     /// Get entry with the given key
-    pub fn get<T: ToString>(&self, key: T) -> Option<&Entry> {
+    pub fn get<T: ToString>(&self, key: T) -> Option<&Entry<B>> {
         self.entries.get(&key.to_string())
     }
 
     /// Remove entry with the given key
-    pub fn remove<T: ToString>(&mut self, key: T) -> Option<Entry> {
+    pub fn remove<T: ToString>(&mut self, key: T) -> Option<Entry<B>> {
         self.entries.remove(&key.to_string())
     }
 
     /// Try to map entry with specific key
-    /// 
+    ///
     /// ```
     /// use kinda_virtual_fs::*;
-    /// 
+    ///
     /// let mut storage = Storage::default();
-    /// 
+    ///
     /// storage.add("example", "Hello, World!");
-    /// 
+    ///
     /// let file_path = storage.map("example").expect("Failed to map entry");
     /// let file_content = std::fs::read_to_string(file_path).expect("Failed to read mapped entry");
-    /// 
+    ///
     /// assert_eq!(&file_content, "Hello, World!");
     /// ```
     pub fn map<T: ToString>(&self, key: T) -> std::io::Result<String> {
         match self.get(key.to_string()) {
             Some(entry) => entry.map(),
-            None => Err(Error::new(ErrorKind::Other, format!("No entry with key {} found", key.to_string())))
+            None => Err(Error::other(format!("No entry with key {} found", key.to_string())))
         }
     }
 
     /// Unmap entry with specific key
-    /// 
+    ///
     /// Will return `Ok(())` if there's no entry with specified key
-    /// 
+    ///
     /// Entry will be automatically unmapped when its value is no more needed
-    /// 
+    ///
     /// ## Manual unmapping
-    /// 
+    ///
     /// ```
     /// use kinda_virtual_fs::*;
-    /// 
+    ///
     /// let mut storage = Storage::default();
-    /// 
+    ///
     /// storage.add("example", "Hello, World!");
-    /// 
+    ///
     /// let path = storage.map("example").unwrap();
-    /// 
+    ///
     /// assert_eq!(std::path::Path::new(&path).exists(), true);
-    /// 
+    ///
     /// storage.unmap("example");
-    /// 
+    ///
     /// assert_eq!(std::path::Path::new(&path).exists(), false);
     /// ```
-    /// 
+    ///
     /// ## Automatic unmapping
-    /// 
+    ///
     /// ```
     /// use kinda_virtual_fs::*;
-    /// 
+    ///
     /// let mut storage = Storage::default();
-    /// 
+    ///
     /// storage.add("example", "Hello, World!");
-    /// 
+    ///
     /// let path = storage.map("example").unwrap();
-    /// 
+    ///
     /// assert_eq!(std::path::Path::new(&path).exists(), true);
-    /// 
+    ///
     /// storage.remove("example");
-    /// 
+    ///
     /// assert_eq!(std::path::Path::new(&path).exists(), false);
     /// ```
     pub fn unmap<T: ToString>(&self, key: T) -> std::io::Result<()> {
@@ -228,4 +906,120 @@ impl Storage {
             None => Ok(())
         }
     }
+
+    /// Drop every expired entry, unmapping its physical file
+    ///
+    /// Gives callers a cheap way to reclaim disk for time-bounded data without
+    /// waiting for each [`Entry`] to be dropped individually.
+    ///
+    /// ```
+    /// use kinda_virtual_fs::*;
+    /// use std::time::Duration;
+    ///
+    /// let mut storage = Storage::default();
+    ///
+    /// storage.add("temp", Entry::new_with_ttl("cached", Duration::from_secs(0)));
+    ///
+    /// storage.purge_expired();
+    ///
+    /// assert_eq!(storage.get("temp").is_none(), true);
+    /// ```
+    pub fn purge_expired(&mut self) {
+        let expired = self.entries.iter()
+            .filter(|(_, entry)| entry.is_expired())
+            .map(|(key, _)| key.clone())
+            .collect::<Vec<_>>();
+
+        for key in expired {
+            if let Some(entry) = self.entries.remove(&key) {
+                let _ = entry.unmap();
+            }
+        }
+    }
+
+    /// Serialize every key and its raw bytes into a single self-describing archive
+    ///
+    /// The layout is a small header (a magic tag and a format version) followed
+    /// by a length-prefixed sequence of `(key_len, key, value_len, value)`
+    /// records. Reload it with [`Storage::from_bytes`].
+    ///
+    /// Expired entries are skipped: the archive carries no TTL, so persisting
+    /// them would resurrect time-bounded data as permanent entries on reload,
+    /// defeating [`Storage::purge_expired`].
+    ///
+    /// ```
+    /// use kinda_virtual_fs::*;
+    ///
+    /// let mut storage = Storage::default();
+    ///
+    /// storage.add("greeting", "Hello, World!");
+    ///
+    /// let bytes = storage.to_bytes();
+    /// let restored = Storage::from_bytes(&bytes).unwrap();
+    ///
+    /// assert_eq!(restored.get("greeting").unwrap().bytes(), b"Hello, World!");
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+
+        out.extend_from_slice(ARCHIVE_MAGIC);
+        out.extend_from_slice(&ARCHIVE_VERSION.to_le_bytes());
+
+        for (key, entry) in self.entries.iter().filter(|(_, entry)| !entry.is_expired()) {
+            let key = key.as_bytes();
+            let value = entry.bytes();
+
+            out.extend_from_slice(&(key.len() as u64).to_le_bytes());
+            out.extend_from_slice(key);
+            out.extend_from_slice(&(value.len() as u64).to_le_bytes());
+            out.extend_from_slice(value);
+        }
+
+        out
+    }
+
+    /// Save the whole storage to `path` as an archive (see [`Storage::to_bytes`])
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        std::fs::write(path, self.to_bytes())
+    }
+}
+
+impl Storage<TempDirBackend> {
+    /// Restore a storage from an archive produced by [`Storage::to_bytes`]
+    ///
+    /// The reader is version-tolerant: it checks the magic tag and format
+    /// version and dispatches to the matching layout parser, so archives
+    /// written by an older build can still be loaded by a newer one. Restored
+    /// entries use the default [`TempDirBackend`].
+    pub fn from_bytes(bytes: &[u8]) -> std::io::Result<Self> {
+        let mut reader = ArchiveReader::new(bytes);
+
+        if reader.take(ARCHIVE_MAGIC.len())? != ARCHIVE_MAGIC {
+            return Err(Error::new(ErrorKind::InvalidData, "not a kvfs archive"));
+        }
+
+        let version = reader.take_u32()?;
+
+        let records = match version {
+            1 => read_records_v1(&mut reader)?,
+
+            other => return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("unsupported kvfs archive version {other}")
+            ))
+        };
+
+        let mut entries = HashMap::with_capacity(records.len());
+
+        for (key, value) in records {
+            entries.insert(key, Entry::new(value));
+        }
+
+        Ok(Self { entries })
+    }
+
+    /// Load a storage from an archive file (see [`Storage::from_bytes`])
+    pub fn load<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        Self::from_bytes(&std::fs::read(path)?)
+    }
 }